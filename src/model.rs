@@ -1,10 +1,126 @@
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::ops::{Index, IndexMut};
+use yew::services::interval::IntervalTask;
+use yew::services::websocket::WebSocketTask;
+use yew::ComponentLink;
 
 /// A position on the rustsweeper cell.
 pub type Pos = (u8, u8);
 
+/// A selectable difficulty preset, pairing board dimensions with a mine count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] =
+        [Difficulty::Beginner, Difficulty::Intermediate, Difficulty::Expert];
+
+    /// Returns the (width, height, number of mines) for this difficulty.
+    pub fn dimensions(self) -> (u8, u8, u8) {
+        match self {
+            Difficulty::Beginner => (9, 9, 10),
+            Difficulty::Intermediate => (16, 16, 40),
+            Difficulty::Expert => (24, 24, 99),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Intermediate => "Intermediate",
+            Difficulty::Expert => "Expert",
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Beginner
+    }
+}
+
+impl ::std::str::FromStr for Difficulty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL.iter().find(|d| d.name() == s).cloned().ok_or(())
+    }
+}
+
+/// The best completion time recorded per difficulty, keyed by `Difficulty::name`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scores(HashMap<String, u32>);
+
+impl Scores {
+    /// The best recorded time for a difficulty, if any game was ever won on it.
+    pub fn best(&self, difficulty: Difficulty) -> Option<u32> {
+        self.0.get(difficulty.name()).cloned()
+    }
+
+    /// Records a completion time, keeping it only if it beats the existing best.
+    /// Returns whether this time became the new record; a time equal to the existing best does not.
+    pub fn record(&mut self, difficulty: Difficulty, seconds: u32) -> bool {
+        match self.0.get(difficulty.name()) {
+            Some(&best) if best <= seconds => false,
+            _ => {
+                self.0.insert(difficulty.name().to_string(), seconds);
+                true
+            }
+        }
+    }
+
+    /// Discards all recorded best times.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Whether the board is played solo or mirrored live with a remote peer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mode {
+    Local,
+    Networked { paired: bool, phrase: Option<String> },
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Local
+    }
+}
+
+/// A keyboard navigation direction for moving the focused cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A single player action recorded on `Field::history`, replayable against a fresh board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    Reveal(Pos),
+    ToggleMark(Pos),
+    Chord(Pos),
+}
+
+impl Move {
+    fn pos(self) -> Pos {
+        match self {
+            Move::Reveal(pos) | Move::ToggleMark(pos) | Move::Chord(pos) => pos,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum CellState {
     Marked,
@@ -18,6 +134,25 @@ impl Default for CellState {
     }
 }
 
+impl CellState {
+    fn to_number(&self) -> u8 {
+        match self {
+            CellState::Unmarked => 0,
+            CellState::Marked => 1,
+            CellState::Revealed => 2,
+        }
+    }
+
+    fn from_number(number: u8) -> Option<CellState> {
+        match number {
+            0 => Some(CellState::Unmarked),
+            1 => Some(CellState::Marked),
+            2 => Some(CellState::Revealed),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Cell {
     pub mine: bool,
@@ -31,13 +166,58 @@ impl Cell {
         self.neighbors = 0;
         self.state = CellState::Unmarked;
     }
+
+    /// Packs this cell into a single byte: bit 7 is the mine flag, bits 6-5 the `CellState`, and
+    /// bits 3-0 the neighbor count (0-8 fits in 4 bits).
+    fn to_byte(&self) -> u8 {
+        let mine_bit = if self.mine { 0x80 } else { 0x00 };
+        let state_bits = self.state.to_number() << 5;
+        mine_bit | state_bits | (self.neighbors & 0x0F)
+    }
+
+    /// The inverse of `to_byte`, rejecting bytes whose state bits don't map to a `CellState`.
+    fn from_byte(byte: u8) -> Option<Cell> {
+        let mine = byte & 0x80 != 0;
+        let state = CellState::from_number((byte >> 5) & 0x03)?;
+        let neighbors = byte & 0x0F;
+        Some(Cell { mine, neighbors, state })
+    }
 }
 
 /// The current game state of Rustsweeper.
 pub struct Field {
     pub width: u8,
     pub height: u8,
+    pub difficulty: Difficulty,
+    /// Seconds elapsed since the first reveal, advanced by `tick`.
+    pub elapsed: u32,
+    /// Best completion times per difficulty, loaded from and persisted to local storage by the UI.
+    pub scores: Scores,
+    /// Whether the current (won) game beat the previously stored record.
+    pub new_record: bool,
+    /// Whether this is a solo game or a live co-op session mirrored with a peer.
+    pub mode: Mode,
+    /// The cell currently focused for keyboard navigation.
+    pub focus: Pos,
+    /// Every `reveal`/`toggle_marked`/`chord` applied so far, in order, replayable via `replay`.
+    pub history: Vec<Move>,
+    pub(crate) link: Option<ComponentLink<Field>>,
+    pub(crate) tick_task: Option<IntervalTask>,
+    pub(crate) ws_task: Option<WebSocketTask>,
+    /// This client's tie-breaker for the co-op host handshake, sent to the peer as
+    /// `NetMessage::Hello` once paired (and again after every restart) to decide `co_op_host`.
+    pub(crate) co_op_nonce: Option<u64>,
+    /// Set once the co-op host handshake resolves: `Some(true)` if this client was elected to
+    /// place the shared mine layout and broadcast it, `Some(false)` if the peer was. `None`
+    /// before pairing, or while a fresh handshake (e.g. after a restart) is still in flight.
+    pub(crate) co_op_host: Option<bool>,
+    /// The RNG seed used by `prepare_mines`/`prepare_mines_excluding`, if mines were placed
+    /// locally rather than received via `seed_mines`. Needed to reproduce a game from `history`.
+    mines_seed: Option<u64>,
     cells: Vec<Cell>,
+    /// Work buffer for the transitive reveal flood fill, kept around and cleared rather than
+    /// reallocated on every `reveal`.
+    reveal_scratch: Vec<Pos>,
 }
 
 impl Index<Pos> for Field {
@@ -65,12 +245,72 @@ const NEIGHBOR_POS: &[(i32, i32); 8] = &[
     (1, 1),
 ];
 
+/// A stack-allocated, fixed-capacity list of up to 8 neighbor positions, so `iter_neighbors`
+/// doesn't need a heap allocation per call.
+#[derive(Clone, Copy)]
+struct Neighbors {
+    positions: [Pos; 8],
+    len: u8,
+}
+
+impl Neighbors {
+    fn new() -> Neighbors {
+        Neighbors { positions: [(0, 0); 8], len: 0 }
+    }
+
+    fn push(&mut self, pos: Pos) {
+        self.positions[usize::from(self.len)] = pos;
+        self.len += 1;
+    }
+}
+
+impl IntoIterator for Neighbors {
+    type Item = Pos;
+    type IntoIter = NeighborsIter;
+
+    fn into_iter(self) -> NeighborsIter {
+        NeighborsIter { neighbors: self, index: 0 }
+    }
+}
+
+struct NeighborsIter {
+    neighbors: Neighbors,
+    index: u8,
+}
+
+impl Iterator for NeighborsIter {
+    type Item = Pos;
+
+    fn next(&mut self) -> Option<Pos> {
+        if self.index < self.neighbors.len {
+            let pos = self.neighbors.positions[usize::from(self.index)];
+            self.index += 1;
+            Some(pos)
+        } else {
+            None
+        }
+    }
+}
+
 impl Field {
     /// Creates a new instance of the Rustsweeper game with a given width and height.
     pub fn new(width: u8, height: u8) -> Field {
         Field {
             width,
             height,
+            difficulty: Difficulty::default(),
+            elapsed: 0,
+            scores: Scores::default(),
+            new_record: false,
+            mode: Mode::default(),
+            focus: (0, 0),
+            history: Vec::new(),
+            link: None,
+            tick_task: None,
+            ws_task: None,
+            co_op_nonce: None,
+            co_op_host: None,
+            mines_seed: None,
             cells: vec![
                 Cell {
                     state: CellState::Unmarked,
@@ -79,43 +319,104 @@ impl Field {
                 };
                 usize::from(width) * usize::from(height)
             ],
+            reveal_scratch: Vec::new(),
         }
     }
 
+    /// Creates a new field sized for the given difficulty. The mines still need to be placed
+    /// with `prepare_mines` using `difficulty.dimensions().2`.
+    pub fn with_difficulty(difficulty: Difficulty) -> Field {
+        let (width, height, _) = difficulty.dimensions();
+        let mut field = Field::new(width, height);
+        field.difficulty = difficulty;
+        field
+    }
+
     /// Resets the game state while preserving the dimensions.
     pub fn reset(&mut self) {
         for cell in self.cells.iter_mut() {
             cell.reset();
         }
+        self.elapsed = 0;
+        self.tick_task = None;
+        self.new_record = false;
+        self.history.clear();
+        self.mines_seed = None;
+    }
+
+    /// Advances the elapsed-time clock by one tick.
+    pub fn tick(&mut self) {
+        self.elapsed = self.elapsed.saturating_add(1);
+    }
+
+    /// Places mines at the exact positions given (used to mirror a remote-seeded layout in
+    /// networked co-op) and recalculates neighbor counts.
+    pub fn seed_mines(&mut self, positions: &[Pos]) {
+        for &pos in positions {
+            self[pos].mine = true;
+        }
+        self.calc_neighbors();
     }
 
-    fn place_mine<R: Rng>(&mut self, rng: &mut R) {
+    /// Returns the positions of all mines on the field.
+    pub fn mine_positions(&self) -> Vec<Pos> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&pos| self[pos].mine)
+            .collect()
+    }
+
+    /// Returns the number of mines not yet accounted for by a flag. Can go negative if the
+    /// player has placed more flags than there are mines.
+    pub fn mines_remaining(&self) -> i32 {
+        let marked = self
+            .cells
+            .iter()
+            .filter(|cell| cell.state == CellState::Marked)
+            .count() as i32;
+        let mines = self.cells.iter().filter(|cell| cell.mine).count() as i32;
+        mines - marked
+    }
+
+    fn place_mine<R: Rng>(&mut self, rng: &mut R, forbidden: &[Pos]) {
         loop {
             let x: u8 = rng.gen_range(0, self.width);
             let y: u8 = rng.gen_range(0, self.height);
-            if !self[(x, y)].mine {
+            if !self[(x, y)].mine && !forbidden.contains(&(x, y)) {
                 self[(x, y)].mine = true;
                 break;
             }
         }
     }
 
-    /// Places the given number of mines randomly on the field.
-    pub fn place_mines<R: Rng>(&mut self, number: u8, rng: &mut R) {
+    /// Places the given number of mines randomly on the field, never on a `forbidden` position.
+    pub fn place_mines<R: Rng>(&mut self, number: u8, rng: &mut R, forbidden: &[Pos]) {
         for _ in 0..number {
-            self.place_mine(rng)
+            self.place_mine(rng, forbidden)
         }
     }
 
-    fn iter_neighbors(&self, (x, y): Pos) -> impl Iterator<Item = (u8, u8)> {
-        let width = self.width;
-        let height = self.height;
-        NEIGHBOR_POS.iter().filter_map(move |(rx, ry)| {
-            match (u8::try_from(i32::from(x) + rx), u8::try_from(i32::from(y) + ry)) {
-                (Ok(unx), Ok(uny)) if unx < width && uny < height => Some((unx, uny)),
-                _ => None,
+    /// Moves the keyboard focus one cell in the given direction, clamped to the board edges.
+    pub fn move_focus(&mut self, dir: Direction) {
+        let (x, y) = self.focus;
+        self.focus = match dir {
+            Direction::Up => (x, y.saturating_sub(1)),
+            Direction::Down => (x, (y + 1).min(self.height - 1)),
+            Direction::Left => (x.saturating_sub(1), y),
+            Direction::Right => ((x + 1).min(self.width - 1), y),
+        };
+    }
+
+    pub(crate) fn iter_neighbors(&self, (x, y): Pos) -> impl Iterator<Item = Pos> {
+        let mut neighbors = Neighbors::new();
+        for (rx, ry) in NEIGHBOR_POS.iter() {
+            if let (Ok(unx), Ok(uny)) = (u8::try_from(i32::from(x) + rx), u8::try_from(i32::from(y) + ry)) {
+                if unx < self.width && uny < self.height {
+                    neighbors.push((unx, uny));
+                }
             }
-        })
+        }
+        neighbors.into_iter()
     }
 
     /// Calculates the number of neighboring mines of all cells.
@@ -130,13 +431,15 @@ impl Field {
         }
     }
 
-    fn lost(&self) -> bool {
+    /// Whether a mine has been revealed.
+    pub fn lost(&self) -> bool {
         self.cells
             .iter()
             .any(|cell| cell.state == CellState::Revealed && cell.mine)
     }
 
-    fn won(&self) -> bool {
+    /// Whether every non-mine cell has been revealed.
+    pub fn won(&self) -> bool {
         self.cells
             .iter()
             .all(|cell| cell.state == CellState::Revealed || cell.mine)
@@ -149,7 +452,12 @@ impl Field {
 
     /// Returns a message that summarizes the game state.
     pub fn message(&self) -> String {
-        if self.lost() {
+        let status = match &self.mode {
+            Mode::Networked { paired: true, .. } => "Connected. ",
+            Mode::Networked { paired: false, .. } => "Connecting... ",
+            Mode::Local => "",
+        };
+        let state = if self.lost() {
             String::from("Game lost!")
         } else if self.won() {
             String::from("Game won!")
@@ -161,7 +469,8 @@ impl Field {
                 .count();
             let mines = self.cells.iter().filter(|cell| cell.mine).count();
             format!("Found {} of {} mines.", marked, mines)
-        }
+        };
+        format!("{}{}", status, state)
     }
 
     fn reveal_transitive(&mut self, pos: Pos, todo: &mut Vec<Pos>) {
@@ -175,16 +484,50 @@ impl Field {
         }
     }
 
-    /// Reveals the cell at the given position and transitively reveals all other connected cells
-    /// with 0 neighboring mines.
-    pub fn reveal(&mut self, pos: Pos) {
+    /// The unrecorded core of `reveal`, also used by `chord` so a chord logs a single `Move`
+    /// instead of one per revealed neighbor.
+    fn reveal_cell(&mut self, pos: Pos) {
         self[pos].state = CellState::Revealed;
         if self[pos].neighbors == 0 && !self[pos].mine {
-            let mut todo = vec![pos];
+            let mut todo = std::mem::take(&mut self.reveal_scratch);
+            todo.clear();
+            todo.push(pos);
             while let Some(next) = todo.pop() {
                 self.reveal_transitive(next, &mut todo);
             }
+            todo.clear();
+            self.reveal_scratch = todo;
+        }
+    }
+
+    /// Reveals the cell at the given position and transitively reveals all other connected cells
+    /// with 0 neighboring mines.
+    pub fn reveal(&mut self, pos: Pos) {
+        self.reveal_cell(pos);
+        self.history.push(Move::Reveal(pos));
+    }
+
+    /// The standard "clear" convenience move: if `pos` is `Revealed` with a positive neighbor
+    /// count and the number of `Marked` neighbors equals it, reveals every remaining `Unmarked`
+    /// neighbor. If a flag was placed incorrectly this can legitimately reveal a mine.
+    pub fn chord(&mut self, pos: Pos) {
+        let cell = &self[pos];
+        if cell.state != CellState::Revealed || cell.neighbors == 0 {
+            return;
+        }
+        let marked = self
+            .iter_neighbors(pos)
+            .filter(|&n| self[n].state == CellState::Marked)
+            .count() as u8;
+        if marked != self[pos].neighbors {
+            return;
+        }
+        for neighbor in self.iter_neighbors(pos).collect::<Vec<_>>() {
+            if self[neighbor].state == CellState::Unmarked {
+                self.reveal_cell(neighbor);
+            }
         }
+        self.history.push(Move::Chord(pos));
     }
 
     pub fn toggle_marked(&mut self, pos: Pos) {
@@ -192,6 +535,16 @@ impl Field {
             CellState::Marked => CellState::Unmarked,
             CellState::Unmarked => CellState::Marked,
             CellState::Revealed => CellState::Revealed,
+        };
+        self.history.push(Move::ToggleMark(pos));
+    }
+
+    /// Applies a previously recorded move, used to rebuild a field in `replay`/`undo`.
+    fn apply_move(&mut self, mv: Move) {
+        match mv {
+            Move::Reveal(pos) => self.reveal(pos),
+            Move::ToggleMark(pos) => self.toggle_marked(pos),
+            Move::Chord(pos) => self.chord(pos),
         }
     }
 
@@ -202,16 +555,206 @@ impl Field {
     }
 
     pub fn prepare_mines(&mut self, number_of_mines: u8) {
-        self.place_mines(number_of_mines, &mut thread_rng());
+        let seed = thread_rng().gen();
+        self.prepare_mines_seeded(number_of_mines, seed, None);
+    }
+
+    /// Prepares mines while keeping `first` and its neighborhood mine-free, so the first reveal
+    /// always opens a satisfying starting region. Falls back to excluding only `first` if the
+    /// board is too dense for the full exclusion zone.
+    pub fn prepare_mines_excluding(&mut self, number_of_mines: u8, first: Pos) {
+        let seed = thread_rng().gen();
+        self.prepare_mines_seeded(number_of_mines, seed, Some(first));
+    }
+
+    /// The seedable core of `prepare_mines`/`prepare_mines_excluding`, remembering the seed so the
+    /// layout can be reproduced by `replay`.
+    fn prepare_mines_seeded(&mut self, number_of_mines: u8, seed: u64, first: Option<Pos>) {
+        let forbidden = match first {
+            Some(pos) => {
+                let mut forbidden: Vec<Pos> = self.iter_neighbors(pos).collect();
+                forbidden.push(pos);
+                let available = usize::from(self.width) * usize::from(self.height) - forbidden.len();
+                if available < usize::from(number_of_mines) {
+                    vec![pos]
+                } else {
+                    forbidden
+                }
+            }
+            None => Vec::new(),
+        };
+        self.place_mines(number_of_mines, &mut StdRng::seed_from_u64(seed), &forbidden);
         self.calc_neighbors();
+        self.mines_seed = Some(seed);
+    }
+
+    /// Deterministically reconstructs a field from the mine seed and recorded move list, e.g. for
+    /// replays or shareable solutions. The first `Reveal`/`Chord` move, if any, anchors the same
+    /// first-click exclusion zone that `prepare_mines_excluding` would have used originally — a
+    /// leading `ToggleMark` doesn't call `ensure_mines`, so it's not the real anchor and is
+    /// skipped when looking for it.
+    pub fn replay(width: u8, height: u8, number_of_mines: u8, mines_seed: u64, moves: &[Move]) -> Field {
+        let mut field = Field::new(width, height);
+        let first = moves.iter().find(|mv| !matches!(mv, Move::ToggleMark(_))).map(|mv| mv.pos());
+        field.prepare_mines_seeded(number_of_mines, mines_seed, first);
+        for &mv in moves {
+            field.apply_move(mv);
+        }
+        field
+    }
+
+    /// Rewinds the last move by replaying every move but the last from a fresh board using the
+    /// same mine seed. A no-op if there is no history, or the mines were received from a peer
+    /// rather than placed locally (no seed to reproduce them from). Carries over the running
+    /// `ws_task`/`tick_task` (and co-op handshake state) along with `mode`/`link`, so undo
+    /// doesn't drop a live connection or stall the clock mid-game.
+    pub fn undo(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let seed = match self.mines_seed {
+            Some(seed) => seed,
+            None => return,
+        };
+        let number_of_mines = self.mine_positions().len() as u8;
+        let moves = self.history[..self.history.len() - 1].to_vec();
+        let difficulty = self.difficulty;
+        let scores = self.scores.clone();
+        let mode = self.mode.clone();
+        let link = self.link.take();
+        let tick_task = self.tick_task.take();
+        let ws_task = self.ws_task.take();
+        let co_op_nonce = self.co_op_nonce;
+        let co_op_host = self.co_op_host;
+        let mut field = Field::replay(self.width, self.height, number_of_mines, seed, &moves);
+        field.difficulty = difficulty;
+        field.scores = scores;
+        field.mode = mode;
+        field.link = link;
+        field.tick_task = tick_task;
+        field.ws_task = ws_task;
+        field.co_op_nonce = co_op_nonce;
+        field.co_op_host = co_op_host;
+        *self = field;
+    }
+
+    /// For each revealed numbered cell, the still-`Unmarked` neighbors and how many of them must
+    /// be mines, i.e. `neighbors - (marked neighbor count)`.
+    fn constraints(&self) -> Vec<(Vec<Pos>, u8)> {
+        let mut constraints = Vec::new();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let pos = (x, y);
+                let cell = &self[pos];
+                if cell.state != CellState::Revealed || cell.neighbors == 0 {
+                    continue;
+                }
+                let marked = self
+                    .iter_neighbors(pos)
+                    .filter(|&n| self[n].state == CellState::Marked)
+                    .count() as u8;
+                let unknown: Vec<Pos> = self
+                    .iter_neighbors(pos)
+                    .filter(|&n| self[n].state == CellState::Unmarked)
+                    .collect();
+                if !unknown.is_empty() {
+                    constraints.push((unknown, cell.neighbors.saturating_sub(marked)));
+                }
+            }
+        }
+        constraints
+    }
+
+    /// Derives the positions that are certainly safe to reveal and certainly mines, using the
+    /// basic per-cell rule plus the subset rule across pairs of constraints. Never guesses: a
+    /// position is only returned once its state is fully determined.
+    pub fn deduce(&self) -> (Vec<Pos>, Vec<Pos>) {
+        let constraints = self.constraints();
+        let mut safe = Vec::new();
+        let mut mines = Vec::new();
+        for (unknown, remaining) in &constraints {
+            if *remaining == 0 {
+                safe.extend(unknown.iter().cloned());
+            } else if *remaining as usize == unknown.len() {
+                mines.extend(unknown.iter().cloned());
+            }
+        }
+        for (a, remaining_a) in &constraints {
+            for (b, remaining_b) in &constraints {
+                if a.len() < b.len() && a.iter().all(|p| b.contains(p)) {
+                    let diff: Vec<Pos> = b.iter().cloned().filter(|p| !a.contains(p)).collect();
+                    let diff_mines = remaining_b.saturating_sub(*remaining_a);
+                    if diff_mines == 0 {
+                        safe.extend(diff.iter().cloned());
+                    } else if diff_mines as usize == diff.len() {
+                        mines.extend(diff.iter().cloned());
+                    }
+                }
+            }
+        }
+        safe.sort();
+        safe.dedup();
+        mines.sort();
+        mines.dedup();
+        (safe, mines)
+    }
+
+    /// Applies one round of logical deduction: reveals every cell provably safe and flags every
+    /// cell provably a mine. Returns whether any progress was made.
+    pub fn solve_step(&mut self) -> bool {
+        let (safe, mines) = self.deduce();
+        for &pos in &safe {
+            if self[pos].state == CellState::Unmarked {
+                self.reveal(pos);
+            }
+        }
+        for &pos in &mines {
+            if self[pos].state == CellState::Unmarked {
+                self.toggle_marked(pos);
+            }
+        }
+        !safe.is_empty() || !mines.is_empty()
+    }
+
+    /// Repeatedly applies `solve_step` until no further progress can be made without guessing.
+    pub fn solve_to_fixpoint(&mut self) {
+        while self.solve_step() {}
+    }
+
+    /// Encodes the grid as a compact byte string: a `(width, height)` header followed by one
+    /// packed byte per cell, suitable for local storage or sharing via a short string.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.cells.len());
+        bytes.push(self.width);
+        bytes.push(self.height);
+        bytes.extend(self.cells.iter().map(Cell::to_byte));
+        bytes
+    }
+
+    /// The inverse of `to_bytes`. Returns `None` if the header is missing or the cell data's
+    /// length doesn't match `width * height`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Field> {
+        if bytes.len() < 2 {
+            return None;
+        }
+        let (width, height) = (bytes[0], bytes[1]);
+        let cell_bytes = &bytes[2..];
+        if cell_bytes.len() != usize::from(width) * usize::from(height) {
+            return None;
+        }
+        let cells = cell_bytes
+            .iter()
+            .map(|&byte| Cell::from_byte(byte))
+            .collect::<Option<Vec<Cell>>>()?;
+        let mut field = Field::new(width, height);
+        field.cells = cells;
+        Some(field)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::rngs::StdRng;
-    use rand::SeedableRng;
 
     #[test]
     fn new() {
@@ -221,6 +764,20 @@ mod tests {
         assert_eq!(field.cells.len(), 3 * 5);
     }
 
+    #[test]
+    fn with_difficulty() {
+        let field = Field::with_difficulty(Difficulty::Intermediate);
+        assert_eq!(field.width, 16);
+        assert_eq!(field.height, 16);
+        assert_eq!(field.difficulty, Difficulty::Intermediate);
+    }
+
+    #[test]
+    fn difficulty_from_str() {
+        assert_eq!("Expert".parse(), Ok(Difficulty::Expert));
+        assert_eq!("Nonsense".parse::<Difficulty>(), Err(()));
+    }
+
     #[test]
     fn index() {
         let field = Field::new(3, 5);
@@ -247,7 +804,7 @@ mod tests {
     fn place_mine() {
         let mut rng = StdRng::seed_from_u64(23);
         let mut field = Field::new(3, 5);
-        field.place_mine(&mut rng);
+        field.place_mine(&mut rng, &[]);
         assert!(field[(0, 4)].mine);
     }
 
@@ -255,11 +812,43 @@ mod tests {
     fn place_mines() {
         let mut rng = StdRng::seed_from_u64(23);
         let mut field = Field::new(3, 5);
-        field.place_mines(4, &mut rng);
+        field.place_mines(4, &mut rng, &[]);
         let mines = field.cells.iter().filter(|cell| cell.mine).count();
         assert_eq!(mines, 4)
     }
 
+    #[test]
+    fn place_mines_avoids_forbidden_positions() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut field = Field::new(3, 5);
+        field.place_mines(4, &mut rng, &[(0, 4), (1, 4), (2, 4)]);
+        let mines = field.cells.iter().filter(|cell| cell.mine).count();
+        assert_eq!(mines, 4);
+        assert!(!field[(0, 4)].mine);
+        assert!(!field[(1, 4)].mine);
+        assert!(!field[(2, 4)].mine);
+    }
+
+    #[test]
+    fn prepare_mines_excluding_keeps_first_click_safe() {
+        let mut field = Field::new(9, 9);
+        field.prepare_mines_excluding(10, (4, 4));
+        assert!(!field[(4, 4)].mine);
+        for neighbor in field.iter_neighbors((4, 4)).collect::<Vec<_>>() {
+            assert!(!field[neighbor].mine);
+        }
+        assert_eq!(field[(4, 4)].neighbors, 0);
+    }
+
+    #[test]
+    fn prepare_mines_excluding_falls_back_when_board_too_small() {
+        let mut field = Field::new(3, 3);
+        field.prepare_mines_excluding(1, (1, 1));
+        let mines = field.cells.iter().filter(|cell| cell.mine).count();
+        assert_eq!(mines, 1);
+        assert!(!field[(1, 1)].mine);
+    }
+
     macro_rules! assert_neighbors {
         ( $field:ident | $y:ident | ( $( $n:literal ),* ) ) => {{
             let mut x = 0;
@@ -327,4 +916,214 @@ mod tests {
         assert!(first_row.is_some());
         assert_eq!(first_row.unwrap().len(), 3);
     }
+
+    #[test]
+    fn tick() {
+        let mut field = Field::new(3, 5);
+        field.tick();
+        field.tick();
+        assert_eq!(field.elapsed, 2);
+    }
+
+    #[test]
+    fn mines_remaining() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut field = Field::new(3, 5);
+        field.place_mines(4, &mut rng, &[]);
+        assert_eq!(field.mines_remaining(), 4);
+        field.toggle_marked((0, 4));
+        assert_eq!(field.mines_remaining(), 3);
+    }
+
+    #[test]
+    fn scores_record() {
+        let mut scores = Scores::default();
+        assert_eq!(scores.best(Difficulty::Beginner), None);
+        assert!(scores.record(Difficulty::Beginner, 42));
+        assert_eq!(scores.best(Difficulty::Beginner), Some(42));
+        assert!(!scores.record(Difficulty::Beginner, 50));
+        assert_eq!(scores.best(Difficulty::Beginner), Some(42));
+        assert!(scores.record(Difficulty::Beginner, 10));
+        assert_eq!(scores.best(Difficulty::Beginner), Some(10));
+    }
+
+    #[test]
+    fn scores_clear() {
+        let mut scores = Scores::default();
+        scores.record(Difficulty::Expert, 100);
+        scores.clear();
+        assert_eq!(scores.best(Difficulty::Expert), None);
+    }
+
+    #[test]
+    fn move_focus() {
+        let mut field = Field::new(3, 5);
+        assert_eq!(field.focus, (0, 0));
+        field.move_focus(Direction::Up);
+        assert_eq!(field.focus, (0, 0));
+        field.move_focus(Direction::Right);
+        field.move_focus(Direction::Down);
+        assert_eq!(field.focus, (1, 1));
+        for _ in 0..10 {
+            field.move_focus(Direction::Right);
+            field.move_focus(Direction::Down);
+        }
+        assert_eq!(field.focus, (2, 4));
+    }
+
+    #[test]
+    fn seed_mines() {
+        let mut field = Field::new(3, 5);
+        field.seed_mines(&[(0, 0), (2, 4)]);
+        let mut positions = field.mine_positions();
+        positions.sort();
+        assert_eq!(positions, vec![(0, 0), (2, 4)]);
+    }
+
+    #[test]
+    fn chord() {
+        let mut field = Field::new(3, 3);
+        field[(1, 1)].mine = true;
+        field.calc_neighbors();
+        field.reveal((0, 0));
+        field.toggle_marked((1, 1));
+        field.chord((0, 0));
+        assert_eq!(field[(1, 0)].state, CellState::Revealed);
+        assert_eq!(field[(0, 1)].state, CellState::Revealed);
+    }
+
+    #[test]
+    fn chord_does_nothing_when_flags_do_not_match() {
+        let mut field = Field::new(3, 3);
+        field[(1, 1)].mine = true;
+        field.calc_neighbors();
+        field.reveal((0, 0));
+        field.chord((0, 0));
+        assert_eq!(field[(1, 0)].state, CellState::Unmarked);
+        assert_eq!(field[(0, 1)].state, CellState::Unmarked);
+    }
+
+    #[test]
+    fn solve_step_basic_rule() {
+        // . . .
+        // . X .
+        // . . .
+        let mut field = Field::new(3, 3);
+        field[(1, 1)].mine = true;
+        field.calc_neighbors();
+        field.reveal((0, 0));
+        field.toggle_marked((1, 1));
+        assert!(field.solve_step());
+        assert_eq!(field[(1, 0)].state, CellState::Revealed);
+        assert_eq!(field[(0, 1)].state, CellState::Revealed);
+    }
+
+    #[test]
+    fn solve_to_fixpoint_never_reveals_a_mine() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut field = Field::new(3, 5);
+        field.place_mines(4, &mut rng, &[]);
+        field.calc_neighbors();
+        field.reveal((1, 2));
+        field.solve_to_fixpoint();
+        assert!(!field.lost());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut field = Field::new(3, 5);
+        field.place_mines(4, &mut rng, &[]);
+        field.calc_neighbors();
+        field.reveal((1, 4));
+        field.toggle_marked((2, 0));
+
+        let restored = Field::from_bytes(&field.to_bytes()).expect("valid bytes");
+        assert_eq!(restored.width, field.width);
+        assert_eq!(restored.height, field.height);
+        for x in 0..field.width {
+            for y in 0..field.height {
+                let original = &field[(x, y)];
+                let copy = &restored[(x, y)];
+                assert_eq!(copy.mine, original.mine);
+                assert_eq!(copy.neighbors, original.neighbors);
+                assert_eq!(copy.state, original.state);
+            }
+        }
+    }
+
+    #[test]
+    fn from_bytes_rejects_length_mismatch() {
+        let field = Field::new(3, 5);
+        let mut bytes = field.to_bytes();
+        bytes.pop();
+        assert!(Field::from_bytes(&bytes).is_none());
+    }
+
+    #[test]
+    fn reveal_and_toggle_marked_append_to_history() {
+        let mut field = Field::new(3, 5);
+        field.reveal((0, 0));
+        field.toggle_marked((1, 0));
+        assert_eq!(field.history, vec![Move::Reveal((0, 0)), Move::ToggleMark((1, 0))]);
+    }
+
+    #[test]
+    fn chord_appends_a_single_history_entry() {
+        let mut field = Field::new(3, 3);
+        field[(1, 1)].mine = true;
+        field.calc_neighbors();
+        field.reveal((0, 0));
+        field.toggle_marked((1, 1));
+        field.chord((0, 0));
+        assert_eq!(
+            field.history,
+            vec![Move::Reveal((0, 0)), Move::ToggleMark((1, 1)), Move::Chord((0, 0))]
+        );
+    }
+
+    #[test]
+    fn replay_reproduces_a_prepared_game() {
+        let mut field = Field::new(4, 4);
+        field.prepare_mines_excluding(3, (0, 0));
+        field.reveal((0, 0));
+        field.toggle_marked((3, 3));
+        let seed = field.mines_seed.expect("mines were placed locally");
+
+        let replayed = Field::replay(4, 4, 3, seed, &field.history);
+        for x in 0..field.width {
+            for y in 0..field.height {
+                assert_eq!(replayed[(x, y)].mine, field[(x, y)].mine);
+                assert_eq!(replayed[(x, y)].state, field[(x, y)].state);
+            }
+        }
+    }
+
+    #[test]
+    fn replay_skips_a_leading_toggle_mark_when_anchoring_the_exclusion_zone() {
+        let mut field = Field::new(4, 4);
+        field.toggle_marked((3, 3));
+        field.prepare_mines_excluding(3, (0, 0));
+        field.reveal((0, 0));
+        let seed = field.mines_seed.expect("mines were placed locally");
+
+        let replayed = Field::replay(4, 4, 3, seed, &field.history);
+        for x in 0..field.width {
+            for y in 0..field.height {
+                assert_eq!(replayed[(x, y)].mine, field[(x, y)].mine);
+            }
+        }
+    }
+
+    #[test]
+    fn undo_rewinds_the_last_move() {
+        let mut field = Field::new(4, 4);
+        field.prepare_mines_excluding(3, (0, 0));
+        field.reveal((0, 0));
+        field.toggle_marked((3, 3));
+        assert_eq!(field[(3, 3)].state, CellState::Marked);
+        field.undo();
+        assert_eq!(field[(3, 3)].state, CellState::Unmarked);
+        assert_eq!(field[(0, 0)].state, CellState::Revealed);
+    }
 }
@@ -1,10 +1,13 @@
 use crate::model::CellState;
 use stdweb::traits::IEvent;
+use stdweb::web::event::{IMouseEvent, MouseButton};
 use yew::{html, Callback, Component, ComponentLink, Html, Renderable, ShouldRender};
 
 pub enum Action {
     Reveal,
     ToggleMark,
+    Chord,
+    Noop,
 }
 
 #[derive(PartialEq, Clone, Default)]
@@ -13,8 +16,10 @@ pub struct Cell {
     pub neighbors: u8,
     pub state: CellState,
     pub game_over: bool,
+    pub focused: bool,
     pub onreveal: Option<Callback<()>>,
     pub onmark: Option<Callback<()>>,
+    pub onchord: Option<Callback<()>>,
 }
 
 impl Component for Cell {
@@ -26,15 +31,17 @@ impl Component for Cell {
     }
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
-        if self.state == props.state && self.game_over == props.game_over {
+        if self.state == props.state && self.game_over == props.game_over && self.focused == props.focused {
             return false;
         }
         self.state = props.state;
         self.mine = props.mine;
         self.neighbors = props.neighbors;
         self.game_over = props.game_over;
+        self.focused = props.focused;
         self.onreveal = props.onreveal;
         self.onmark = props.onmark;
+        self.onchord = props.onchord;
         true
     }
 
@@ -60,16 +67,32 @@ impl Component for Cell {
                     false
                 }
             }
+            Action::Chord => {
+                if self.state == CellState::Revealed && self.neighbors > 0 {
+                    self.onchord.as_ref().map_or(false, |s| {
+                        s.emit(());
+                        true
+                    })
+                } else {
+                    false
+                }
+            }
+            Action::Noop => false,
         }
     }
 }
 
 fn cell_to_class(cell: &Cell) -> String {
-    match cell {
-        Cell { game_over: true, state: CellState::Marked, mine: true, .. } => String::from("correct"),
-        Cell { game_over: true, state: CellState::Marked, mine: false, .. } => String::from("incorrect"),
-        Cell { game_over: false, state, .. } if *state != CellState::Revealed => String::from("unknown"),
-        _ => String::new()
+    let base = match cell {
+        Cell { game_over: true, state: CellState::Marked, mine: true, .. } => "correct",
+        Cell { game_over: true, state: CellState::Marked, mine: false, .. } => "incorrect",
+        Cell { game_over: false, state, .. } if *state != CellState::Revealed => "unknown",
+        _ => "",
+    };
+    if cell.focused {
+        format!("{} focused", base).trim().to_string()
+    } else {
+        String::from(base)
     }
 }
 
@@ -85,12 +108,14 @@ fn cell_to_str(cell: &Cell) -> String {
 
 impl Renderable<Cell> for Cell {
     fn view(&self) -> Html<Self> {
+        let revealed_number = self.state == CellState::Revealed && self.neighbors > 0;
         html! {
             <td>
                 <button
                 class=cell_to_class(&self)
                 disabled=self.game_over
-                onclick=|_| Action::Reveal
+                onclick=move |_| if revealed_number { Action::Chord } else { Action::Reveal }
+                onmousedown=|e| if e.button() == MouseButton::Wheel { Action::Chord } else { Action::Noop }
                 oncontextmenu=|e| { e.prevent_default(); Action::ToggleMark }>
                 { cell_to_str(&self) }
                 </button>
@@ -1,58 +1,309 @@
-use crate::model::{Cell, Field, Pos};
+use crate::model::{Cell, Difficulty, Direction, Field, Mode, Pos};
 use crate::ui::cell::Cell as CellComponent;
-use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
-
-const NUMBER_OF_MINES: u8 = 10;
+use crate::ui::net::{self, NetMessage};
+use crate::ui::storage::{load_scores, save_scores};
+use rand::{thread_rng, Rng};
+use stdweb::traits::{IEvent, IKeyboardEvent};
+use std::str::FromStr;
+use std::time::Duration;
+use yew::format::Json;
+use yew::services::IntervalService;
+use yew::{html, ChangeData, Component, ComponentLink, Html, Renderable, ShouldRender};
 
 pub enum Action {
     Reveal(Pos),
     ToggleMark(Pos),
+    Chord(Pos),
     Restart,
+    SetDifficulty(Difficulty),
+    Tick,
+    ClearScores,
+    Join(String),
+    Connected,
+    Disconnected,
+    Remote(NetMessage),
+    MoveFocus(Direction),
+    RevealFocused,
+    MarkFocused,
+    Noop,
 }
 
 impl Component for Field {
     type Message = Action;
     type Properties = ();
 
-    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
-        let mut field = Field::new(9, 9);
-        field.prepare_mines(NUMBER_OF_MINES);
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut field = new_field(Difficulty::default());
+        field.scores = load_scores();
+        field.link = Some(link);
         field
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Action::Reveal(pos) => {
-                self.reveal(pos);
+                if self.ensure_mines(pos) {
+                    self.reveal(pos);
+                    self.sync_timer();
+                    self.record_if_won();
+                    self.broadcast(NetMessage::Reveal(pos));
+                }
             }
             Action::ToggleMark(pos) => {
                 self.toggle_marked(pos);
+                self.broadcast(NetMessage::ToggleMark(pos));
+            }
+            Action::Chord(pos) => {
+                self.chord(pos);
+                self.sync_timer();
+                self.record_if_won();
+                self.broadcast(NetMessage::Chord(pos));
             }
             Action::Restart => {
                 self.reset();
-                self.prepare_mines(NUMBER_OF_MINES);
+                self.broadcast(NetMessage::Restart);
+                if matches!(self.mode, Mode::Networked { .. }) {
+                    self.start_handshake();
+                }
+            }
+            Action::SetDifficulty(difficulty) => {
+                let link = self.link.take();
+                let scores = self.scores.clone();
+                *self = new_field(difficulty);
+                self.link = link;
+                self.scores = scores;
+            }
+            Action::Tick => {
+                self.tick();
+            }
+            Action::ClearScores => {
+                self.scores.clear();
+                save_scores(&self.scores);
+            }
+            Action::Join(phrase) => {
+                self.mode = Mode::Networked { paired: false, phrase: Some(phrase.clone()) };
+                if let Some(link) = self.link.clone() {
+                    self.ws_task = Some(net::connect(
+                        &phrase,
+                        &link,
+                        Action::Remote,
+                        || Action::Connected,
+                        || Action::Disconnected,
+                    ));
+                }
+            }
+            Action::Connected => {
+                if let Mode::Networked { paired, .. } = &mut self.mode {
+                    *paired = true;
+                }
+                self.start_handshake();
+            }
+            Action::Disconnected => {
+                self.mode = Mode::Local;
+                self.ws_task = None;
+            }
+            Action::Remote(message) => self.apply_remote(message),
+            Action::MoveFocus(dir) => {
+                self.move_focus(dir);
+            }
+            Action::RevealFocused => {
+                let pos = self.focus;
+                if self.ensure_mines(pos) {
+                    self.reveal(pos);
+                    self.sync_timer();
+                    self.record_if_won();
+                    self.broadcast(NetMessage::Reveal(pos));
+                }
+            }
+            Action::MarkFocused => {
+                let pos = self.focus;
+                self.toggle_marked(pos);
+                self.broadcast(NetMessage::ToggleMark(pos));
             }
+            Action::Noop => {}
         }
         true
     }
 }
 
-fn view_cell(x: usize, y: usize, cell: &Cell, game_over: bool) -> Html<Field> {
+fn new_field(difficulty: Difficulty) -> Field {
+    Field::with_difficulty(difficulty)
+}
+
+trait MineControl {
+    /// Places the difficulty's mines on the first reveal of a fresh board, keeping `first` safe.
+    /// A no-op once mines are already on the field, or, in a networked co-op game, while the host
+    /// handshake hasn't elected this client (`co_op_host == Some(true)`) to place them — that
+    /// covers both losing the election and the window before it resolves. In a co-op game this
+    /// also broadcasts the resulting layout via `NetMessage::Seed`.
+    ///
+    /// Returns whether the field now actually has mines placed, i.e. whether it's safe to reveal
+    /// from. Callers must skip `reveal`/`record_if_won` when this is `false`, or they'd flood-fill
+    /// reveal a zero-mine board (every cell's `neighbors` defaults to 0) and record a bogus score.
+    fn ensure_mines(&mut self, first: Pos) -> bool;
+}
+
+impl MineControl for Field {
+    fn ensure_mines(&mut self, first: Pos) -> bool {
+        if !self.mine_positions().is_empty() {
+            return true;
+        }
+        if matches!(self.mode, Mode::Networked { .. }) && self.co_op_host != Some(true) {
+            return false;
+        }
+        let (_, _, mines) = self.difficulty.dimensions();
+        self.prepare_mines_excluding(mines, first);
+        if matches!(self.mode, Mode::Networked { .. }) {
+            let positions = self.mine_positions();
+            self.broadcast(NetMessage::Seed(positions));
+        }
+        true
+    }
+}
+
+trait NetworkControl {
+    /// Sends a move to the paired peer, if a co-op session is connected.
+    fn broadcast(&mut self, message: NetMessage);
+    /// Applies a move received from the peer without re-broadcasting it.
+    fn apply_remote(&mut self, message: NetMessage);
+    /// (Re-)starts the co-op host handshake: draws a fresh tie-breaker and sends it as
+    /// `NetMessage::Hello`, clearing any previously elected host until the peer's reply resolves
+    /// who seeds the mines this round.
+    fn start_handshake(&mut self);
+}
+
+impl NetworkControl for Field {
+    fn broadcast(&mut self, message: NetMessage) {
+        if let Mode::Networked { paired: true, .. } = self.mode {
+            if let Some(task) = self.ws_task.as_mut() {
+                task.send(Json(&message));
+            }
+        }
+    }
+
+    fn apply_remote(&mut self, message: NetMessage) {
+        match message {
+            NetMessage::Hello(peer_nonce) => match self.co_op_nonce {
+                Some(nonce) if nonce > peer_nonce => self.co_op_host = Some(true),
+                Some(nonce) if nonce < peer_nonce => self.co_op_host = Some(false),
+                _ => self.start_handshake(),
+            },
+            NetMessage::Seed(positions) => {
+                self.reset();
+                self.seed_mines(&positions);
+            }
+            NetMessage::Reveal(pos) => {
+                if self.ensure_mines(pos) {
+                    self.reveal(pos);
+                    self.sync_timer();
+                    self.record_if_won();
+                }
+            }
+            NetMessage::ToggleMark(pos) => {
+                self.toggle_marked(pos);
+            }
+            NetMessage::Chord(pos) => {
+                self.chord(pos);
+                self.sync_timer();
+                self.record_if_won();
+            }
+            NetMessage::Restart => {
+                self.reset();
+                self.start_handshake();
+            }
+        }
+    }
+
+    fn start_handshake(&mut self) {
+        self.co_op_host = None;
+        let nonce = thread_rng().gen();
+        self.co_op_nonce = Some(nonce);
+        self.broadcast(NetMessage::Hello(nonce));
+    }
+}
+
+trait ScoreControl {
+    /// Records the elapsed time as a best score if the game was just won, persisting it.
+    fn record_if_won(&mut self);
+}
+
+impl ScoreControl for Field {
+    fn record_if_won(&mut self) {
+        if self.won() && !self.lost() {
+            self.new_record = self.scores.record(self.difficulty, self.elapsed);
+            save_scores(&self.scores);
+        }
+    }
+}
+
+trait TimerControl {
+    /// Starts the interval on the first reveal and stops it once the game is over.
+    fn sync_timer(&mut self);
+}
+
+impl TimerControl for Field {
+    fn sync_timer(&mut self) {
+        if self.game_over() {
+            self.tick_task = None;
+        } else if self.tick_task.is_none() {
+            if let Some(link) = self.link.clone() {
+                let callback = link.send_back(|_| Action::Tick);
+                self.tick_task = Some(IntervalService::new().spawn(Duration::from_secs(1), callback));
+            }
+        }
+    }
+}
+
+fn keydown_action(key: &str) -> Action {
+    match key {
+        "ArrowUp" => Action::MoveFocus(Direction::Up),
+        "ArrowDown" => Action::MoveFocus(Direction::Down),
+        "ArrowLeft" => Action::MoveFocus(Direction::Left),
+        "ArrowRight" => Action::MoveFocus(Direction::Right),
+        " " => Action::RevealFocused,
+        "f" | "F" => Action::MarkFocused,
+        _ => Action::Noop,
+    }
+}
+
+/// The column header label for the given 0-based column index, e.g. `0 -> "1"`.
+fn column_label(x: usize) -> String {
+    (x + 1).to_string()
+}
+
+/// The row header label for the given 0-based row index, e.g. `0 -> "A"`, `26 -> "AA"`.
+fn row_label(mut y: usize) -> String {
+    let mut label = Vec::new();
+    loop {
+        label.push(b'A' + (y % 26) as u8);
+        if y < 26 {
+            break;
+        }
+        y = y / 26 - 1;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap()
+}
+
+fn view_cell(x: usize, y: usize, cell: &Cell, game_over: bool, focus: Pos) -> Html<Field> {
     html! {
         <CellComponent
           state=cell.state.clone()
           mine=cell.mine
           neighbors=cell.neighbors
           game_over=game_over
+          focused=focus == (x as u8, y as u8)
           onreveal=move |_| Action::Reveal((x as u8, y as u8))
-          onmark=move |_| Action::ToggleMark((x as u8, y as u8)) />
+          onmark=move |_| Action::ToggleMark((x as u8, y as u8))
+          onchord=move |_| Action::Chord((x as u8, y as u8)) />
     }
 }
 
-fn view_row(y: usize, row: &[Cell], game_over: bool) -> Html<Field> {
+fn view_row(y: usize, row: &[Cell], game_over: bool, focus: Pos) -> Html<Field> {
     html! {
         <tr>
-            { for row.iter().enumerate().map(|(x, cell)| view_cell(x, y, cell, game_over))  }
+            <th>{ row_label(y) }</th>
+            { for row.iter().enumerate().map(|(x, cell)| view_cell(x, y, cell, game_over, focus))  }
         </tr>
     }
 }
@@ -62,22 +313,105 @@ fn view_grid(field: &Field) -> Html<Field> {
     let grid = field.to_field();
     html! {
         <table>
-            { for grid.iter().enumerate().map(|(y, row)| view_row(y, row, game_over) )  }
+            <tr>
+                <th></th>
+                { for (0..field.width as usize).map(|x| html! { <th>{ column_label(x) }</th> }) }
+            </tr>
+            { for grid.iter().enumerate().map(|(y, row)| view_row(y, row, game_over, field.focus) )  }
         </table>
     }
 }
 
+fn view_difficulty_option(difficulty: Difficulty, current: Difficulty) -> Html<Field> {
+    html! {
+        <option selected=difficulty == current>{ difficulty.name() }</option>
+    }
+}
+
+fn view_difficulty_select(field: &Field) -> Html<Field> {
+    let current = field.difficulty;
+    html! {
+        <select
+          onkeydown=|e| { e.stop_propagation(); Action::Noop }
+          onchange=|cd| {
+            match cd {
+                ChangeData::Select(se) => Action::SetDifficulty(
+                    Difficulty::from_str(&se.value().unwrap_or_default()).unwrap_or(current)
+                ),
+                _ => Action::SetDifficulty(current),
+            }
+        }>
+            { for Difficulty::ALL.iter().map(|&d| view_difficulty_option(d, current)) }
+        </select>
+    }
+}
+
+fn view_score(difficulty: Difficulty, field: &Field) -> Html<Field> {
+    let best = field.scores.best(difficulty);
+    let is_current_record = field.new_record && field.difficulty == difficulty;
+    html! {
+        <li class=if is_current_record { "new-record" } else { "" }>
+            { difficulty.name() }
+            { ": " }
+            { best.map_or(String::from("-"), |seconds| format!("{}s", seconds)) }
+        </li>
+    }
+}
+
+fn view_scores(field: &Field) -> Html<Field> {
+    html! {
+        <section class="scores">
+            <h2>{ "Best scores" }</h2>
+            <ul>
+                { for Difficulty::ALL.iter().map(|&d| view_score(d, field)) }
+            </ul>
+            <button onclick=|_| Action::ClearScores>{ "Clear scores" }</button>
+        </section>
+    }
+}
+
+fn view_co_op(field: &Field) -> Html<Field> {
+    match &field.mode {
+        Mode::Local => html! {
+            <div class="co-op">
+                <input
+                  type="text"
+                  placeholder="join phrase"
+                  onkeydown=|e| { e.stop_propagation(); Action::Noop }
+                  onchange=|cd| match cd {
+                      ChangeData::Value(phrase) if !phrase.is_empty() => Action::Join(phrase),
+                      _ => Action::Noop,
+                  } />
+            </div>
+        },
+        Mode::Networked { paired, phrase } => html! {
+            <div class="co-op">
+                { format!(
+                    "Co-op \"{}\": {}",
+                    phrase.clone().unwrap_or_default(),
+                    if *paired { "connected" } else { "connecting..." }
+                ) }
+            </div>
+        },
+    }
+}
+
 impl Renderable<Field> for Field {
     fn view(&self) -> Html<Self> {
         html! {
-            <main>
+            <main onkeydown=|e| keydown_action(&e.key()) tabindex="0">
                 <h1>{ "Rustsweeper" }</h1>
                 <nav>
                     <button onclick=|_| Action::Restart>{ "Restart" }</button>
+                    { view_difficulty_select(self) }
+                    <span class="timer">{ format!("{}s", self.elapsed) }</span>
+                    <span class="mine-counter">{ self.mines_remaining() }</span>
                     <p>{ self.message() }</p>
+                    { view_co_op(self) }
                     <div style="clear:both"></div>
                 </nav>
                 { view_grid(self) }
+                { view_scores(self) }
             </main>
         }
     }
@@ -0,0 +1,51 @@
+use crate::model::Pos;
+use serde::{Deserialize, Serialize};
+use stdweb::web::window;
+use yew::format::Json;
+use yew::services::websocket::{WebSocketService, WebSocketStatus, WebSocketTask};
+use yew::ComponentLink;
+
+/// A move relayed verbatim between the two clients of a networked co-op game.
+///
+/// `Hello` carries a random tie-breaker exchanged right after pairing (and again after every
+/// restart) so the two clients can agree on which one is the `co_op_host` responsible for
+/// placing mines. `Seed` is then sent once, by that host only, so both boards place mines at
+/// the same positions; every other variant mirrors the matching `Action`.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum NetMessage {
+    Hello(u64),
+    Seed(Vec<Pos>),
+    Reveal(Pos),
+    ToggleMark(Pos),
+    Chord(Pos),
+    Restart,
+}
+
+/// Opens a co-op session relay for the given join phrase. Incoming moves are re-dispatched via
+/// `on_message`, a successful handshake via `on_open`, and a dropped connection via
+/// `on_disconnect`.
+pub fn connect<M, O, D>(
+    phrase: &str,
+    link: &ComponentLink<crate::model::Field>,
+    on_message: M,
+    on_open: O,
+    on_disconnect: D,
+) -> WebSocketTask
+where
+    M: Fn(NetMessage) -> <crate::model::Field as yew::Component>::Message + 'static,
+    O: Fn() -> <crate::model::Field as yew::Component>::Message + 'static,
+    D: Fn() -> <crate::model::Field as yew::Component>::Message + Clone + 'static,
+{
+    let host = window().location().map(|l| l.host().unwrap_or_default()).unwrap_or_default();
+    let url = format!("ws://{}/co-op/{}", host, phrase);
+    let disconnected_on_error = on_disconnect.clone();
+    let data_callback = link.send_back(move |Json(data)| match data {
+        Ok(message) => on_message(message),
+        Err(_) => disconnected_on_error(),
+    });
+    let notification_callback = link.send_back(move |status| match status {
+        WebSocketStatus::Opened => on_open(),
+        WebSocketStatus::Closed | WebSocketStatus::Error => on_disconnect(),
+    });
+    WebSocketService::new().connect(&url, data_callback, notification_callback)
+}
@@ -0,0 +1,21 @@
+use crate::model::Scores;
+use stdweb::web::{window, IStorage};
+
+const SCORES_KEY: &str = "rustsweeper.scores";
+
+/// Loads the best scores from `localStorage`, falling back to an empty set if nothing was
+/// stored yet or the stored value could not be parsed.
+pub fn load_scores() -> Scores {
+    window()
+        .local_storage()
+        .get(SCORES_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Persists the best scores to `localStorage`.
+pub fn save_scores(scores: &Scores) {
+    if let Ok(raw) = serde_json::to_string(scores) {
+        let _ = window().local_storage().insert(SCORES_KEY, &raw);
+    }
+}